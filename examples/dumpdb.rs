@@ -22,4 +22,12 @@ fn main() {
 
     assert_eq!(database1.len(), database2.len());
 
+    println!("Exporting Vendor database as JSON");
+    let json_dump = database1.export_json().unwrap();
+
+    println!("Importing Vendor database from JSON");
+    let database3 = OuiDatabase::new_from_json(&json_dump).unwrap();
+
+    assert_eq!(database1.len(), database3.len());
+
 }
\ No newline at end of file
@@ -23,4 +23,8 @@ fn main() {
     let res2 = db.query_by_str("00:00:18:00:20:01").unwrap();
     println!("Query result is {:#?}", res2);
 
+    // Locally-administered (randomized) addresses skip the vendor lookup entirely
+    let res3 = db.query_by_str("02:00:00:00:00:01").unwrap();
+    println!("Query result is {:#?}", res3);
+
 }
\ No newline at end of file
@@ -32,11 +32,13 @@ extern crate log;
 extern crate serde_derive;
 
 use std::collections::BTreeMap;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::prelude::*;
-use std::io::BufReader;
+use std::io::{BufReader, Cursor};
+use std::time::Duration;
 
 use byteorder::{NetworkEndian, ReadBytesExt};
+use csv::ReaderBuilder;
 use eui48::MacAddress;
 use failure::{Error, ResultExt};
 use regex::Regex;
@@ -52,6 +54,23 @@ pub struct OuiEntry {
     pub name_long: Option<String>,
     /// Wireshark comment field [OPTIONAL]
     pub comment: Option<String>,
+    /// Registrant's postal address, as published in the IEEE registry CSV [OPTIONAL]
+    pub company_address: Option<String>,
+    /// Registrant's ISO 3166 country code [OPTIONAL]. Not populated by the bulk IEEE CSV
+    /// exports (only the interactive registry search carries it) or by the Wireshark
+    /// loader - left open for richer sources or hand-edited JSON (see
+    /// [`new_from_json`](OuiDatabase::new_from_json)).
+    pub country_code: Option<String>,
+    /// IEEE assignment block size this entry came from - one of `MA-L`, `MA-M`, `MA-S`, `IAB` [OPTIONAL]
+    pub assignment_block_size: Option<String>,
+    /// Date the assignment was first registered, `YYYY-MM-DD` [OPTIONAL]. Same caveat as
+    /// `country_code`: not populated by the bulk IEEE CSV exports.
+    pub date_created: Option<String>,
+    /// Date the assignment was last updated, `YYYY-MM-DD` [OPTIONAL]. Same caveat as
+    /// `country_code`: not populated by the bulk IEEE CSV exports.
+    pub date_updated: Option<String>,
+    /// `true` when the IEEE registry withholds the registrant's name/address for this block
+    pub is_private: bool,
 }
 
 impl Default for OuiEntry {
@@ -60,13 +79,95 @@ impl Default for OuiEntry {
             name_short: String::new(),
             name_long: None,
             comment: None,
+            company_address: None,
+            country_code: None,
+            assignment_block_size: None,
+            date_created: None,
+            date_updated: None,
+            is_private: false,
         }
     }
 }
 
+/// Indicates where the data behind a [`OuiDatabase::new_from_url`] load actually came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataSource {
+    /// A local cache within `max_age` was reused; nothing was downloaded
+    Cache,
+    /// Fresh data was downloaded over HTTP
+    Network,
+    /// The network fetch failed, so a stale local cache was used instead
+    StaleCache,
+}
+
+/// Classification of a MAC address's scope, derived from the U/L and multicast bits of its
+/// first octet - independent of whether the address falls within a known OUI block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacScope {
+    /// A genuine, globally-unique OUI assigned by the IEEE
+    GloballyUnique,
+    /// The locally-administered (U/L) bit is set - commonly a randomized/private address
+    /// (e.g. modern phones randomizing their MAC for Wi-Fi privacy), so any "vendor" derived
+    /// from it would be meaningless
+    LocallyAdministered,
+    /// The multicast bit is set; this was never assigned to a single vendor as a unicast OUI
+    Multicast,
+    /// The broadcast address (`ff:ff:ff:ff:ff:ff`)
+    Broadcast,
+}
+
+/// Result of looking up a MAC address: its vendor entry (if any), alongside its `MacScope`
+#[derive(Debug, Clone)]
+pub struct MacLookup {
+    /// The matched vendor entry, or `None` if the address isn't in a globally-unique OUI
+    /// block we know about - including when `scope` isn't `MacScope::GloballyUnique`, since
+    /// such addresses are skipped rather than queried
+    pub entry: Option<OuiEntry>,
+    /// The address's scope classification
+    pub scope: MacScope,
+}
+
+/// Classifies a MAC address's scope from the U/L and multicast bits of its first octet
+fn classify_mac_scope(mac: &MacAddress) -> MacScope {
+    let bytes = mac.as_bytes();
+    if bytes == [0xFF; 6] {
+        return MacScope::Broadcast;
+    }
+    let first_octet = bytes[0];
+    if first_octet & 0x01 != 0 {
+        MacScope::Multicast
+    } else if first_octet & 0x02 != 0 {
+        MacScope::LocallyAdministered
+    } else {
+        MacScope::GloballyUnique
+    }
+}
+
+/// Selects how `query_by_name` matches a vendor name against the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameMatchMode {
+    /// The supplied name must equal a `name_short` or `name_long` exactly (case-insensitive)
+    Exact,
+    /// The supplied name must appear anywhere within a `name_short` or `name_long` (case-insensitive)
+    Substring,
+}
+
 /// OUI Database
 pub struct OuiDatabase {
     database: OuiMap,
+    /// Auxiliary index keyed on range start only (start -> end), so `query` can walk
+    /// candidates in descending order instead of scanning every entry. The matched
+    /// `OuiEntry` itself is re-fetched from `database` on a hit rather than cloned
+    /// into this map too. Where several entries share a start, the widest end is kept.
+    starts: BTreeMap<u64, u64>,
+    /// For each key in `starts`, the widest `end` among all entries with a start <=
+    /// that key. Lets `query` detect "no enclosing range at all" in O(log n) without
+    /// any backward scan, and guarantees that when a scan *is* needed, it's bounded
+    /// by an actual match rather than a magic depth cap.
+    prefix_max_end: BTreeMap<u64, u64>,
+    /// Auxiliary index mapping lowercased vendor names to the ranges they own,
+    /// so `query_by_name` doesn't have to scan the whole database.
+    name_index: BTreeMap<String, Vec<(u64, u64)>>,
 }
 
 impl OuiDatabase {
@@ -74,16 +175,185 @@ impl OuiDatabase {
     pub fn new_from_file(dbfile: &str) -> Result<OuiDatabase, Error> {
         let db = create_oui_db_from_file(dbfile)?;
         info!("Created a new OUI Vendor database from file {}", dbfile);
-        Ok(OuiDatabase { database: db })
+        Ok(OuiDatabase::from_map(db))
     }
 
     /// Create a new database from a previously exported `Vec<u8>`
     pub fn new_from_export(data: &[u8]) -> Result<OuiDatabase, Error> {
         let deserialized = bincode::deserialize(data).context("could not deserialize data")?;
         info!("Created a new OUI Vendor database from previously exported data");
-        Ok(OuiDatabase {
-            database: deserialized,
-        })
+        Ok(OuiDatabase::from_map(deserialized))
+    }
+
+    /// Create a new database from a previously exported JSON string (see [`export_json`](OuiDatabase::export_json))
+    pub fn new_from_json(data: &str) -> Result<OuiDatabase, Error> {
+        let records: Vec<JsonOuiRecord> =
+            serde_json::from_str(data).context("could not deserialize JSON data")?;
+
+        let mut database = OuiMap::new();
+        for record in records {
+            let (lo, hi) = parse_oui_prefix(&record.oui)?;
+            database.insert((lo, hi), record.into());
+        }
+
+        info!("Created a new OUI Vendor database from previously exported JSON data");
+        Ok(OuiDatabase::from_map(database))
+    }
+
+    /// Create a new database from one of the public IEEE registry CSV exports (`oui.csv`,
+    /// `mam.csv`, `oui36.csv` or `iab.csv`, covering MA-L, MA-M, MA-S and IAB respectively)
+    ///
+    /// Unlike [`new_from_file`](OuiDatabase::new_from_file), this format carries the
+    /// registrant's postal address. Both loaders populate the same underlying `OuiMap`
+    /// representation; no method combines two databases into one.
+    pub fn new_from_ieee_csv(csvfile: &str) -> Result<OuiDatabase, Error> {
+        let db = create_oui_db_from_ieee_csv(csvfile)?;
+        info!(
+            "Created a new OUI Vendor database from IEEE registry CSV file {}",
+            csvfile
+        );
+        Ok(OuiDatabase::from_map(db))
+    }
+
+    /// Create a new database by downloading the Wireshark `manuf` database over HTTP,
+    /// re-using a local cache when it's still fresh rather than re-downloading.
+    ///
+    /// This only understands the Wireshark `manuf` text format - there's no IEEE CSV
+    /// equivalent yet, since the IEEE registry doesn't publish a single stable URL for
+    /// each bulk export the way Wireshark does for `manuf`. Use
+    /// [`new_from_ieee_csv`](OuiDatabase::new_from_ieee_csv) for a CSV file already on disk.
+    ///
+    /// `cache_path` is where the raw downloaded text is persisted, alongside a sibling
+    /// `bincode` export (`<cache_path>.bin`) so that a fresh process can skip re-parsing
+    /// entirely when the cache is still within `max_age`. If the network is unreachable,
+    /// a stale cache is used as a fallback so offline lookups still work.
+    pub fn new_from_url(
+        url: &str,
+        cache_path: &str,
+        max_age: Duration,
+    ) -> Result<(OuiDatabase, DataSource), Error> {
+        let export_path = format!("{}.bin", cache_path);
+
+        if let Ok(metadata) = fs::metadata(cache_path) {
+            let age = metadata
+                .modified()
+                .context("could not read cache file mtime")?
+                .elapsed()
+                .unwrap_or_default();
+            if age <= max_age {
+                info!("Using fresh local cache {} ({:?} old)", cache_path, age);
+                if let Ok(export) = fs::read(&export_path) {
+                    if let Ok(db) = OuiDatabase::new_from_export(&export) {
+                        return Ok((db, DataSource::Cache));
+                    }
+                }
+                let text = fs::read_to_string(cache_path).context("could not read cache file")?;
+                let db = OuiDatabase::from_map(parse_wireshark_manuf(Cursor::new(text))?);
+                return Ok((db, DataSource::Cache));
+            }
+        }
+
+        match reqwest::blocking::get(url)
+            .and_then(|resp| resp.error_for_status())
+            .and_then(|resp| resp.text())
+        {
+            Ok(text) => {
+                let db = OuiDatabase::from_map(parse_wireshark_manuf(Cursor::new(text.clone()))?);
+                fs::write(cache_path, &text).context("could not write cache file")?;
+                fs::write(&export_path, db.export()?).context("could not write cache export")?;
+                info!("Downloaded fresh OUI Vendor database from {}", url);
+                Ok((db, DataSource::Network))
+            }
+            Err(fetch_err) => {
+                let text = fs::read_to_string(cache_path).context(format!(
+                    "network fetch failed ({}) and no local cache is available",
+                    fetch_err
+                ))?;
+                warn!(
+                    "Network fetch of {} failed ({}); falling back to stale cache {}",
+                    url, fetch_err, cache_path
+                );
+                let db = OuiDatabase::from_map(parse_wireshark_manuf(Cursor::new(text))?);
+                Ok((db, DataSource::StaleCache))
+            }
+        }
+    }
+
+    /// Builds an `OuiDatabase` (and its auxiliary indexes) from a populated `OuiMap`
+    fn from_map(database: OuiMap) -> OuiDatabase {
+        let mut starts = BTreeMap::<u64, u64>::new();
+        let mut prefix_max_end = BTreeMap::<u64, u64>::new();
+        let mut name_index = BTreeMap::<String, Vec<(u64, u64)>>::new();
+
+        // `database` iterates in ascending `(lo, hi)` order, i.e. ascending `lo`, which is
+        // exactly the order `prefix_max_end`'s running maximum needs to be built in.
+        let mut running_max_end = 0u64;
+        for (&(lo, hi), entry) in &database {
+            starts.insert(lo, hi);
+            running_max_end = running_max_end.max(hi);
+            prefix_max_end.insert(lo, running_max_end);
+
+            name_index
+                .entry(entry.name_short.to_lowercase())
+                .or_insert_with(Vec::new)
+                .push((lo, hi));
+            if let Some(name_long) = &entry.name_long {
+                name_index
+                    .entry(name_long.to_lowercase())
+                    .or_insert_with(Vec::new)
+                    .push((lo, hi));
+            }
+        }
+
+        OuiDatabase {
+            database,
+            starts,
+            prefix_max_end,
+            name_index,
+        }
+    }
+
+    /// Finds every OUI block registered to a vendor, matching against both `name_short`
+    /// and `name_long` according to the supplied `NameMatchMode`.
+    ///
+    /// Returns, for each match, the block's starting `MacAddress`, its mask (e.g. `24`
+    /// for a standard MA-L block), and the matched `OuiEntry`.
+    pub fn query_by_name(
+        &self,
+        name: &str,
+        mode: NameMatchMode,
+    ) -> Result<Vec<(MacAddress, u8, OuiEntry)>, Error> {
+        let needle = name.to_lowercase();
+
+        let mut ranges = Vec::<(u64, u64)>::new();
+        match mode {
+            NameMatchMode::Exact => {
+                if let Some(matched) = self.name_index.get(&needle) {
+                    ranges.extend(matched.iter().copied());
+                }
+            }
+            NameMatchMode::Substring => {
+                for (candidate, matched) in &self.name_index {
+                    if candidate.contains(&needle) {
+                        ranges.extend(matched.iter().copied());
+                    }
+                }
+            }
+        }
+        ranges.sort_unstable();
+        ranges.dedup();
+
+        ranges
+            .into_iter()
+            .map(|(lo, hi)| {
+                let entry = self
+                    .database
+                    .get(&(lo, hi))
+                    .cloned()
+                    .ok_or_else(|| format_err!("name index referenced missing range {}-{}", lo, hi))?;
+                Ok((u64_to_mac(lo)?, range_to_mask(lo, hi), entry))
+            })
+            .collect()
     }
 
     /// Export the database to a `Vec<u8>` of bincode bytes
@@ -93,18 +363,52 @@ impl OuiDatabase {
         Ok(data)
     }
 
+    /// Export the database as a human-readable, diffable JSON string
+    ///
+    /// Unlike [`export`](OuiDatabase::export)'s opaque `bincode`, each entry is keyed on its
+    /// reconstructed `AA:BB:CC:DD:EE:FF/NN` OUI prefix rather than a raw `(u64, u64)` tuple,
+    /// and entries are written in the database's sorted order - so the result stays stable
+    /// and reviewable across successive database updates.
+    pub fn export_json(&self) -> Result<String, Error> {
+        let records: Vec<JsonOuiRecord> = self
+            .database
+            .iter()
+            .map(|(&(lo, hi), entry)| (format_oui_prefix(lo, hi), entry.clone()).into())
+            .collect();
+        let data = serde_json::to_string_pretty(&records).context("could not serialize database to JSON")?;
+        info!("Created a JSON dump of the OUI Vendor database for export");
+        Ok(data)
+    }
+
     /// Query the database by `Eui48::MacAddress`
-    pub fn query_by_mac(&self, mac_addr: &MacAddress) -> Result<Option<OuiEntry>, Error> {
+    ///
+    /// Before consulting the vendor database, the address's first octet is inspected to
+    /// classify its `MacScope`. Locally-administered, multicast and broadcast addresses
+    /// aren't globally-unique OUIs, so the database lookup is skipped for them entirely
+    /// rather than returning a misleading vendor hit.
+    pub fn query_by_mac(&self, mac_addr: &MacAddress) -> Result<MacLookup, Error> {
+        let scope = classify_mac_scope(mac_addr);
+        if scope != MacScope::GloballyUnique {
+            debug!(
+                "Skipping OUI Vendor database lookup for {:?}: scope is {:?}",
+                mac_addr, scope
+            );
+            return Ok(MacLookup { entry: None, scope });
+        }
+
         let mac_int = mac_to_u64(mac_addr)?;
         debug!(
             "Querying OUI Vendor database for {:?} ({})",
             mac_addr, mac_int
         );
-        self.query(&mac_int)
+        Ok(MacLookup {
+            entry: self.query(&mac_int)?,
+            scope,
+        })
     }
 
     /// Query the database by `&str`
-    pub fn query_by_str(&self, mac_str: &str) -> Result<Option<OuiEntry>, Error> {
+    pub fn query_by_str(&self, mac_str: &str) -> Result<MacLookup, Error> {
         let mac_addr = MacAddress::parse_str(&mac_str)
             .context(format!("could not parse MAC address from str: {}", mac_str))?;
         self.query_by_mac(&mac_addr)
@@ -125,27 +429,42 @@ impl OuiDatabase {
         // It is possible to have multiple matches for a MAC - this is owing to the
         // IEEE Registration Authority sub-dividing blocks down for new vendors, which
         // results in the first hit being against the larger block, then the manufacturer
-        // specific block matching afterwards. There should never (?!) be more than two matches,
-        // so we'll use the second one if it exists as this wil be the exact manufacturer.
-        let mut results = Vec::<((u64, u64), OuiEntry)>::new();
+        // specific block matching afterwards.
+        //
+        // `prefix_max_end` tells us, in O(log n), the widest `end` among every range whose
+        // start is <= `query`. If that's still less than `query`, no range anywhere can
+        // possibly contain it, so we can bail out without walking anything.
+        let widest_reachable_end = match self.prefix_max_end.range(..=*query).next_back() {
+            Some((_, &max_end)) => max_end,
+            None => return Ok(None),
+        };
+        if widest_reachable_end < *query {
+            return Ok(None);
+        }
 
-        for ((lo, hi), value) in &self.database {
-            if query >= lo && query <= hi {
-                results.push(((*lo, *hi), value.clone()));
+        // A match is guaranteed to exist somewhere among starts <= query, so walking
+        // `starts` backwards from `query` is bounded by an actual hit rather than a magic
+        // depth cap: the first entry whose range still reaches `query` is both guaranteed
+        // to exist and, because IEEE sub-divisions nest fully inside their parent, starts
+        // at the narrowest (most specific) enclosing block's start. Among the entries
+        // sharing that start, the one with the smallest end that still reaches `query`
+        // is the narrowest.
+        for (&lo, &hi) in self.starts.range(..=*query).rev() {
+            if *query <= hi {
+                let entry = self
+                    .database
+                    .range((lo, *query)..=(lo, hi))
+                    .next()
+                    .map(|(_, entry)| entry.clone())
+                    .ok_or_else(|| {
+                        format_err!("starts index referenced missing range {}-{}", lo, hi)
+                    })?;
+                return Ok(Some(entry));
             }
         }
 
-        if results.len() > 2 {
-            return Err(format_err!(
-                "more than two oui matches - possible database error? {:?}",
-                results
-            ));
-        }
-        // Get the last value from the search, and return it
-        match results.pop() {
-            Some(oui_res) => Ok(Some(oui_res.1)),
-            _ => Ok(None),
-        }
+        // Unreachable: `widest_reachable_end` already told us a match exists.
+        Ok(None)
     }
 }
 
@@ -172,14 +491,132 @@ fn mac_to_u64(mac: &MacAddress) -> Result<u64, Error> {
     Ok(mac_num)
 }
 
+/// Converts a `u64` range-start value (as produced by `mac_to_u64`) back to a `MacAddress`
+fn u64_to_mac(val: u64) -> Result<MacAddress, Error> {
+    let bytes = val.to_be_bytes();
+    if bytes[0] != 0 || bytes[1] != 0 {
+        return Err(format_err!("value does not fit in a 48-bit MAC: {}", val));
+    }
+    Ok(MacAddress::new([
+        bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+    ]))
+}
+
+/// Derives the prefix mask (in bits, e.g. `24`) of a range from its stored start/end
+fn range_to_mask(lo: u64, hi: u64) -> u8 {
+    48 - (hi ^ lo).count_ones() as u8
+}
+
+/// A single row of [`OuiDatabase::export_json`]/[`OuiDatabase::new_from_json`], keying an
+/// `OuiEntry` on its reconstructed `AA:BB:CC:DD:EE:FF/NN` OUI prefix rather than a raw
+/// `(u64, u64)` range tuple, so the dump is portable and diffable.
+///
+/// The optional fields are duplicated from `OuiEntry` rather than `#[serde(flatten)]`-ing
+/// it directly, so they can skip emitting `null` for the (common) case where a loader
+/// didn't populate them. `OuiEntry` itself can't opt into `skip_serializing_if` without
+/// also corrupting its `bincode` export, which has no way to represent a field being
+/// absent rather than present-and-default in its fixed positional encoding.
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonOuiRecord {
+    oui: String,
+    name_short: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name_long: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    company_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    country_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assignment_block_size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date_created: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date_updated: Option<String>,
+    #[serde(default)]
+    is_private: bool,
+}
+
+impl From<(String, OuiEntry)> for JsonOuiRecord {
+    fn from((oui, entry): (String, OuiEntry)) -> JsonOuiRecord {
+        JsonOuiRecord {
+            oui,
+            name_short: entry.name_short,
+            name_long: entry.name_long,
+            comment: entry.comment,
+            company_address: entry.company_address,
+            country_code: entry.country_code,
+            assignment_block_size: entry.assignment_block_size,
+            date_created: entry.date_created,
+            date_updated: entry.date_updated,
+            is_private: entry.is_private,
+        }
+    }
+}
+
+impl From<JsonOuiRecord> for OuiEntry {
+    fn from(record: JsonOuiRecord) -> OuiEntry {
+        OuiEntry {
+            name_short: record.name_short,
+            name_long: record.name_long,
+            comment: record.comment,
+            company_address: record.company_address,
+            country_code: record.country_code,
+            assignment_block_size: record.assignment_block_size,
+            date_created: record.date_created,
+            date_updated: record.date_updated,
+            is_private: record.is_private,
+        }
+    }
+}
+
+/// Formats a range's start/end as its `AA:BB:CC:DD:EE:FF/NN` OUI prefix
+fn format_oui_prefix(lo: u64, hi: u64) -> String {
+    let bytes = lo.to_be_bytes();
+    format!(
+        "{:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}/{}",
+        bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7], range_to_mask(lo, hi)
+    )
+}
+
+/// Parses an `AA:BB:CC:DD:EE:FF/NN` OUI prefix back into its `(start, end)` range
+fn parse_oui_prefix(prefix: &str) -> Result<(u64, u64), Error> {
+    let mut parts = prefix.splitn(2, '/');
+    let mac_part = parts
+        .next()
+        .ok_or_else(|| format_err!("empty OUI prefix"))?;
+    let mask: u8 = parts
+        .next()
+        .ok_or_else(|| format_err!("OUI prefix missing mask: {}", prefix))?
+        .parse::<u8>()
+        .context(format!("could not parse mask from OUI prefix: {}", prefix))?;
+    if !(mask >= 8 && mask <= 48) {
+        return Err(format_err!("incorrect mask value in OUI prefix {}: {}", prefix, mask));
+    }
+
+    let mac_addr = MacAddress::parse_str(mac_part)
+        .context(format!("could not parse MAC from OUI prefix: {}", prefix))?;
+    let lo = mac_to_u64(&mac_addr)? & !(0xFFFF_FFFF_FFFF >> mask);
+    let hi = lo | 0xFFFF_FFFF_FFFF >> mask;
+    Ok((lo, hi))
+}
+
 /// Opens and parses a Wireshark data file into a `OuiMap`
 fn create_oui_db_from_file(dbfile: &str) -> Result<OuiMap, Error> {
     let file = File::open(dbfile).context(format!("could not open database file: {}", dbfile))?;
+    parse_wireshark_manuf(BufReader::new(file))
+}
+
+/// Parses Wireshark `manuf`-format data from any `BufRead` source into a `OuiMap`.
+/// Used directly by [`create_oui_db_from_file`] and by the network/cache loader, which
+/// reads from an in-memory buffer instead of a file on disk.
+fn parse_wireshark_manuf<R: BufRead>(reader: R) -> Result<OuiMap, Error> {
     let re = Regex::new("[\t]+").context("could not compile regex")?;
 
     let mut vendor_data = OuiMap::new();
 
-    for line in BufReader::new(file).lines() {
+    for line in reader.lines() {
         let entry = line.context("could not get data line")?;
         // Only process lines with data
         if !(entry.starts_with('#') || entry.is_empty()) {
@@ -266,6 +703,7 @@ fn create_oui_db_from_file(dbfile: &str) -> Result<OuiMap, Error> {
                 name_short,
                 name_long,
                 comment,
+                ..Default::default()
             };
 
             trace!(
@@ -280,3 +718,236 @@ fn create_oui_db_from_file(dbfile: &str) -> Result<OuiMap, Error> {
 
     Ok(vendor_data)
 }
+
+/// Raw row shape of the public IEEE registry CSV exports (`oui.csv`, `mam.csv`,
+/// `oui36.csv`, `iab.csv`): `Registry,Assignment,Organization Name,Organization Address`.
+/// The `Registry` column (`MA-L`/`MA-M`/`MA-S`/`IAB`) tells us the assignment's block
+/// size, so the mask never needs to be inferred from the `Assignment` hex string's
+/// length. Country and assignment-date metadata aren't published in these bulk exports
+/// (only via the interactive registry search), so they have no column here.
+#[derive(Debug, Deserialize)]
+struct IeeeCsvRecord {
+    #[serde(rename = "Registry")]
+    registry: String,
+    #[serde(rename = "Assignment")]
+    assignment: String,
+    #[serde(rename = "Organization Name")]
+    organization_name: String,
+    #[serde(rename = "Organization Address")]
+    organization_address: Option<String>,
+}
+
+/// Returns the prefix mask (in bits) for a given IEEE `Registry` assignment block size
+fn ieee_registry_mask(registry: &str) -> Result<u8, Error> {
+    match registry.to_uppercase().as_str() {
+        "MA-L" => Ok(24),
+        "MA-M" => Ok(28),
+        "MA-S" | "IAB" => Ok(36),
+        other => Err(format_err!("unrecognised IEEE registry block size: {}", other)),
+    }
+}
+
+/// Opens and parses an IEEE registry CSV export into a `OuiMap`
+fn create_oui_db_from_ieee_csv(csvfile: &str) -> Result<OuiMap, Error> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(true)
+        .from_path(csvfile)
+        .context(format!("could not open IEEE registry CSV file: {}", csvfile))?;
+
+    let mut vendor_data = OuiMap::new();
+
+    for record in reader.deserialize() {
+        let record: IeeeCsvRecord = record.context("could not parse IEEE registry CSV row")?;
+
+        let mask = ieee_registry_mask(&record.registry)?;
+
+        let assignment = record
+            .assignment
+            .replace(':', "")
+            .replace('-', "")
+            .to_uppercase();
+        let assignment_int = u64::from_str_radix(&assignment, 16)
+            .context(format!("could not parse assignment hex: {}", assignment))?;
+        let oui_start = assignment_int << (48 - mask);
+        let oui_end = oui_start | 0xFFFF_FFFF_FFFF >> mask;
+
+        let is_private = record.organization_name.eq_ignore_ascii_case("PRIVATE");
+
+        let entry_data = OuiEntry {
+            name_short: record.organization_name.clone(),
+            name_long: Some(record.organization_name),
+            comment: None,
+            company_address: record.organization_address,
+            assignment_block_size: Some(record.registry),
+            is_private,
+            ..Default::default()
+        };
+
+        trace!(
+            "Inserting IEEE registry entry for vendor: Range {}-{} is {:?}",
+            oui_start,
+            oui_end,
+            entry_data
+        );
+        vendor_data.insert((oui_start, oui_end), entry_data);
+    }
+
+    Ok(vendor_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_finds_wider_enclosing_block_past_several_narrower_siblings() {
+        // A /24 parent block, sub-divided right at its start into several narrower
+        // (/36) sibling blocks that don't cover the query address - regression test
+        // for a lookup that gave up after a fixed number of non-matching siblings
+        // instead of continuing on to the parent that actually contains it.
+        let parent_start: u64 = 0xAABBCC_000000;
+        let parent_end: u64 = parent_start | 0xFF_FFFF;
+
+        let mut map = OuiMap::new();
+        map.insert(
+            (parent_start, parent_end),
+            OuiEntry {
+                name_short: "Parent".to_owned(),
+                ..Default::default()
+            },
+        );
+        for i in 1..=5u64 {
+            let child_start = parent_start + i * 0x1000;
+            let child_end = child_start | 0xFFF;
+            map.insert(
+                (child_start, child_end),
+                OuiEntry {
+                    name_short: format!("Child{}", i),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let db = OuiDatabase::from_map(map);
+
+        let query = parent_start + 0x9000;
+        let result = db.query(&query).unwrap();
+        assert_eq!(result.unwrap().name_short, "Parent");
+    }
+
+    #[test]
+    fn query_returns_none_when_no_block_contains_the_address() {
+        let mut map = OuiMap::new();
+        map.insert(
+            (0x0000_0100_0000, 0x0000_01FF_FFFF),
+            OuiEntry::default(),
+        );
+
+        let db = OuiDatabase::from_map(map);
+
+        assert!(db.query(&0x0000_0400_0000).unwrap().is_none());
+    }
+
+    #[test]
+    fn query_by_name_exact_and_substring() {
+        let mut map = OuiMap::new();
+        map.insert(
+            (0x0000_0100_0000, 0x0000_01FF_FFFF),
+            OuiEntry {
+                name_short: "Acme".to_owned(),
+                name_long: Some("Acme Corporation".to_owned()),
+                ..Default::default()
+            },
+        );
+        map.insert(
+            (0x0000_0200_0000, 0x0000_02FF_FFFF),
+            OuiEntry {
+                name_short: "Other".to_owned(),
+                ..Default::default()
+            },
+        );
+
+        let db = OuiDatabase::from_map(map);
+
+        let exact = db.query_by_name("acme", NameMatchMode::Exact).unwrap();
+        assert_eq!(exact.len(), 1);
+        assert_eq!(exact[0].2.name_short, "Acme");
+
+        let substring = db.query_by_name("corp", NameMatchMode::Substring).unwrap();
+        assert_eq!(substring.len(), 1);
+        assert_eq!(substring[0].2.name_short, "Acme");
+
+        let none = db.query_by_name("nomatch", NameMatchMode::Exact).unwrap();
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn classify_mac_scope_globally_unique() {
+        let mac = MacAddress::parse_str("00:1B:63:00:00:00").unwrap();
+        assert_eq!(classify_mac_scope(&mac), MacScope::GloballyUnique);
+    }
+
+    #[test]
+    fn classify_mac_scope_locally_administered() {
+        // U/L bit (0x02) set on the first octet
+        let mac = MacAddress::parse_str("02:00:00:00:00:01").unwrap();
+        assert_eq!(classify_mac_scope(&mac), MacScope::LocallyAdministered);
+    }
+
+    #[test]
+    fn classify_mac_scope_multicast() {
+        // Multicast bit (0x01) set on the first octet
+        let mac = MacAddress::parse_str("01:00:5E:00:00:01").unwrap();
+        assert_eq!(classify_mac_scope(&mac), MacScope::Multicast);
+    }
+
+    #[test]
+    fn classify_mac_scope_broadcast() {
+        let mac = MacAddress::parse_str("FF:FF:FF:FF:FF:FF").unwrap();
+        assert_eq!(classify_mac_scope(&mac), MacScope::Broadcast);
+    }
+
+    #[test]
+    fn json_export_import_round_trip() {
+        let mut map = OuiMap::new();
+        map.insert(
+            (0x0000_0100_0000, 0x0000_01FF_FFFF),
+            OuiEntry {
+                name_short: "Acme".to_owned(),
+                name_long: Some("Acme Corporation".to_owned()),
+                ..Default::default()
+            },
+        );
+        map.insert(
+            (0x0000_0200_0000, 0x0000_0200_0FFF),
+            OuiEntry {
+                name_short: "Other".to_owned(),
+                is_private: true,
+                ..Default::default()
+            },
+        );
+
+        let db = OuiDatabase::from_map(map);
+        let json = db.export_json().unwrap();
+
+        // Fields left unpopulated shouldn't clutter the diffable dump with `null`s.
+        assert!(!json.contains("null"));
+
+        let restored = OuiDatabase::new_from_json(&json).unwrap();
+        assert_eq!(db.len(), restored.len());
+
+        let result = restored
+            .query_by_str("00:00:01:00:00:00")
+            .unwrap()
+            .entry
+            .unwrap();
+        assert_eq!(result.name_short, "Acme");
+        assert_eq!(result.name_long.as_deref(), Some("Acme Corporation"));
+    }
+
+    #[test]
+    fn parse_oui_prefix_rejects_out_of_range_mask() {
+        assert!(parse_oui_prefix("00:1B:63:00:00:00/0").is_err());
+        assert!(parse_oui_prefix("00:1B:63:00:00:00/64").is_err());
+    }
+}